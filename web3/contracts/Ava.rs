@@ -1,19 +1,127 @@
 // SPDX-License-Identifier: MIT
 
 use std::collections::HashMap;
+#[cfg(feature = "events")]
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+#[cfg(feature = "events")]
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use lazy_static::lazy_static;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use ethereum_types::Address;
 use sha3::{Digest, Keccak256};
 use zksync_crypto::params::MAX_CIRCUIT_TREE_DEPTH;
 
+// Game state transitions that downstream UIs/indexers can subscribe to.
+// Kept separate from storage so emitting one is never required for the
+// underlying battle logic to be correct. Only ever constructed from the
+// `events`-gated arm of `emit_event!`, so it's gated the same way the
+// `EventContext::sender` and mpsc/SystemTime imports already are.
+#[cfg(feature = "events")]
+enum GameEvent {
+    NewBattle { name: String, player1: Address, player2: Address },
+    BattleMove { name: String, move_pending: bool },
+    RoundEnded { damaged_players: [Address; 2] },
+    BattleEnded { name: String, winner: Address, loser: Address },
+    NewGameToken { owner: Address, id: u256 },
+}
+
+// Carries the live event sink through the battle functions. `sender` is
+// `None` when nobody has subscribed (or the `events` feature is off), in
+// which case `emit_event!` is a no-op.
+struct EventContext {
+    #[cfg(feature = "events")]
+    sender: Option<mpsc::Sender<(GameEvent, u64)>>,
+}
+
+impl EventContext {
+    fn disconnected() -> EventContext {
+        EventContext {
+            #[cfg(feature = "events")]
+            sender: None,
+        }
+    }
+}
+
+#[cfg(feature = "events")]
+macro_rules! emit_event {
+    ($ctx:expr, $event:expr) => {
+        if let Some(sender) = $ctx.sender.as_ref() {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            if let Err(err) = sender.send(($event, timestamp)) {
+                // The subscriber dropped its receiver; log and keep going,
+                // a missing listener must never fail the battle itself.
+                eprintln!("emit_event: send failed: {}", err);
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "events"))]
+macro_rules! emit_event {
+    ($ctx:expr, $event:expr) => {};
+}
+
 struct GameToken {
     name: String,
     id: u256,
     attack_strength: u256,
     defense_strength: u256,
+    // Turn order within a round: the higher value acts first when both
+    // players attack.
+    initiative: u256,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Element {
+    Devil,
+    Griffin,
+    Firebird,
+    Kamo,
+    Kukulkan,
+    Celestion,
+}
+
+impl Element {
+    fn from_id(id: u256) -> Element {
+        match id.as_u64() % 6u64 {
+            0 => Element::Devil,
+            1 => Element::Griffin,
+            2 => Element::Firebird,
+            3 => Element::Kamo,
+            4 => Element::Kukulkan,
+            _ => Element::Celestion,
+        }
+    }
+
+    fn ordinal(self) -> i32 {
+        self as i32
+    }
+}
+
+// Weak/immune/neutral advantage wheel: each element is strong (2x) against
+// the next one around the cycle and immune (0x) to the one directly
+// opposite it; everything else is neutral (1x).
+fn element_modifier(attacker: Element, defender: Element) -> u256 {
+    let diff = (defender.ordinal() - attacker.ordinal()).rem_euclid(6);
+    match diff {
+        1 => 2u256,
+        3 => 0u256,
+        _ => 1u256,
+    }
+}
+
+// Keeps the attack/defense/initiative branches in resolve_battle readable by
+// folding the element advantage lookup into a single damage number.
+fn effective_attack(attacker_token: &GameToken, defender_token: &GameToken) -> u256 {
+    let modifier = element_modifier(Element::from_id(attacker_token.id), Element::from_id(defender_token.id));
+    attacker_token.attack_strength.clone() * modifier
 }
 
 struct Player {
@@ -24,6 +132,7 @@ struct Player {
     in_battle: bool,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum BattleStatus {
     PENDING,
     STARTED,
@@ -36,6 +145,11 @@ struct Battle {
     name: String,
     players: [Address; 2],
     moves: [u8; 2],
+    // commit-reveal: each player submits keccak256(choice || salt) before
+    // revealing, so neither side can pick their move after seeing the other's.
+    move_commits: [[u8; 32]; 2],
+    revealed_salts: [[u8; 32]; 2],
+    round_counter: u256,
     winner: Address,
 }
 
@@ -55,6 +169,20 @@ lazy_static! {
     static ref KUKULKAN: u256 = 4u256;
     static ref CELESTION: u256 = 5u256;
     static ref MAX_ATTACK_DEFEND_STRENGTH: u256 = 10u256;
+    // Mana is packed into 4 bits by the bit-packed codec, so this is also
+    // the hard cap on how high a defend streak can push it.
+    static ref MAX_MANA: u256 = 15u256;
+}
+
+// Clamps mana gains to `MAX_MANA` so a long defend streak can't grow it
+// past what the bit-packed codec's 4-bit mana field can represent.
+fn add_mana(current: u256, delta: u256) -> u256 {
+    let sum = current + delta;
+    if sum > MAX_MANA.clone() {
+        MAX_MANA.clone()
+    } else {
+        sum
+    }
 }
 
 fn is_player(addr: Address) -> bool {
@@ -108,6 +236,7 @@ fn initialize() {
         id: 0u256,
         attack_strength: 0u256,
         defense_strength: 0u256,
+        initiative: 0u256,
     });
 
     PLAYERS.push(Player {
@@ -124,13 +253,18 @@ fn initialize() {
         name: "".to_string(),
         players: [Address::from([0u8; 20]), Address::from([0u8; 20])],
         moves: [0u8; 2],
+        move_commits: [[0u8; 32]; 2],
+        revealed_salts: [[0u8; 32]; 2],
+        round_counter: 0u256,
         winner: Address::from([0u8; 20]),
     });
 }
 
-fn create_random_num(max: u256, sender: Address) -> u256 {
-    let mut rng = rand::thread_rng();
-    let random_num = rng.gen_range(0..u256::MAX);
+// Reduces a keccak digest into the `[1, max)` range used for attack/defense
+// strength. Centralized so every entropy source (minting, round re-rolls)
+// applies the same "zero means max/2" rule.
+fn reduce_to_strength(max: u256, digest: [u8; 32]) -> u256 {
+    let random_num = u256::from_big_endian(&digest);
     let random_value = random_num % max;
     if random_value == 0u256 {
         max / 2u256
@@ -139,18 +273,55 @@ fn create_random_num(max: u256, sender: Address) -> u256 {
     }
 }
 
-fn create_game_token(name: &str) -> GameToken {
+// Deterministic, auditable stand-in for the old `rand::thread_rng()` call:
+// anyone can recompute it off-chain. Also folds in `TOTAL_SUPPLY` at call
+// time so that minting several tokens from the same `sender` doesn't hash
+// to the same digest every time and produce stat-identical tokens.
+fn create_random_num(max: u256, sender: Address) -> u256 {
+    let mut supply_bytes = [0u8; 32];
+    TOTAL_SUPPLY.to_big_endian(&mut supply_bytes);
+
+    let mut hasher = Keccak256::new();
+    hasher.update(sender.as_bytes());
+    hasher.update(supply_bytes);
+    let digest: [u8; 32] = hasher.finalize().into();
+    reduce_to_strength(max, digest)
+}
+
+// Per-round re-roll entropy: bound to the battle, the round, the player, and
+// the salts both players just revealed, so the result is reproducible by any
+// observer holding the revealed moves but unguessable beforehand.
+fn round_random_num(battle: &Battle, player: Address, max: u256) -> u256 {
+    let mut round_bytes = [0u8; 32];
+    battle.round_counter.to_big_endian(&mut round_bytes);
+
+    let mut hasher = Keccak256::new();
+    hasher.update(battle.battle_hash);
+    hasher.update(round_bytes);
+    hasher.update(player.as_bytes());
+    hasher.update(battle.revealed_salts[0]);
+    hasher.update(battle.revealed_salts[1]);
+    let digest: [u8; 32] = hasher.finalize().into();
+
+    reduce_to_strength(max, digest)
+}
+
+fn create_game_token(name: &str, ctx: &EventContext) -> GameToken {
     let rand_attack_strength = create_random_num(MAX_ATTACK_DEFEND_STRENGTH, sender);
     let rand_defense_strength = MAX_ATTACK_DEFEND_STRENGTH - rand_attack_strength;
 
-    let mut rng = rand::thread_rng();
-    let rand_id = rng.gen_range(0..100u8) % 6u8 + 1u8;
+    // Same deterministic pipeline as the strength rolls above -- the id
+    // feeds Element/initiative (chunk0-2), so leaving it on thread_rng would
+    // still make every battle's elemental modifier and turn order
+    // unreproducible.
+    let rand_id = create_random_num(100u256, sender) % 6u256 + 1u256;
 
     let new_game_token = GameToken {
         name: name.to_string(),
-        id: rand_id.into(),
+        id: rand_id.clone(),
         attack_strength: rand_attack_strength,
         defense_strength: rand_defense_strength,
+        initiative: rand_id,
     };
 
     let token_index = GAME_TOKENS.len() as u256;
@@ -160,12 +331,14 @@ fn create_game_token(name: &str) -> GameToken {
 
     // _mint(sender, rand_id, 1, '0x0'); // Call your token minting function here
 
+    emit_event!(ctx, GameEvent::NewGameToken { owner: sender, id: new_game_token.id.clone() });
+
     new_game_token
 }
 
-fn create_random_game_token(name: &str) {
+fn create_random_game_token(name: &str, ctx: &EventContext) {
     if !getPlayer(sender).in_battle && is_player(sender) {
-        create_game_token(name);
+        create_game_token(name, ctx);
     }
 }
 
@@ -196,7 +369,7 @@ fn create_battle(name: &str) -> Battle {
     }
 }
 
-fn join_battle(name: &str) -> Battle {
+fn join_battle(name: &str, ctx: &EventContext) -> Battle {
     if is_player(sender) {
         let mut battle = get_battle(name).unwrap().clone();
 
@@ -215,8 +388,11 @@ fn join_battle(name: &str) -> Battle {
             PLAYERS[*player1_index as usize].in_battle = true;
             PLAYERS[*player2_index as usize].in_battle = true;
 
-            // Emit NewBattle event (you would need to implement event handling)
-            // emit_new_battle(battle.name, battle.players[0], sender);
+            emit_event!(ctx, GameEvent::NewBattle {
+                name: battle.name.clone(),
+                player1: battle.players[0],
+                player2: sender,
+            });
 
             battle
         } else {
@@ -234,10 +410,54 @@ fn get_battle_moves(battle_name: &str) -> (u8, u8) {
     (battle.moves[0], battle.moves[1])
 }
 
+// Phase 1 of commit-reveal: stash `keccak256(choice || salt)` so the choice
+// itself stays hidden from the opponent until both sides have committed.
+fn commit_player_move(player: u8, commit_hash: [u8; 32], battle_name: &str) {
+    let mut battle = get_battle(battle_name).unwrap().clone();
+    battle.move_commits[player as usize] = commit_hash;
+    update_battle(battle_name, &battle);
+}
+
+// Phase 2 of commit-reveal: the player discloses `(choice, salt)`; once the
+// hash checks out against the stored commitment, the move is registered and
+// the salt is kept around to seed the round's attack/defense re-roll.
+fn reveal_player_move(player: u8, choice: u8, salt: [u8; 32], battle_name: &str) {
+    let battle = get_battle(battle_name).unwrap().clone();
+
+    // Neither side may reveal until both have committed -- otherwise the
+    // first revealer's plaintext choice sits in world-readable
+    // `battle.moves` while the opponent still gets to pick, which is
+    // exactly the information leak commit-reveal exists to prevent.
+    let both_committed = battle.move_commits[0] != [0u8; 32] && battle.move_commits[1] != [0u8; 32];
+
+    let mut hasher = Keccak256::new();
+    hasher.update([choice]);
+    hasher.update(salt);
+    let expected_commit: [u8; 32] = hasher.finalize().into();
+
+    if both_committed && expected_commit == battle.move_commits[player as usize] {
+        let mut battle = battle;
+        battle.revealed_salts[player as usize] = salt;
+        update_battle(battle_name, &battle);
+
+        register_player_move(player, choice, battle_name);
+    } else {
+        // Handle error condition here
+        // e.g., return an error struct or panic
+    }
+}
+
 fn register_player_move(player: u8, choice: u8, battle_name: &str) {
     if choice == 1 || choice == 2 {
-        if choice == 1 && getPlayer(sender).player_mana >= 3 {
-            let mut battle = get_battle(battle_name).unwrap();
+        let battle = get_battle(battle_name).unwrap();
+        // Gate the mana cost against the battle slot that's actually
+        // moving, not the caller -- `take_ai_turn` registers `players[1]`'s
+        // move on its behalf, so checking `sender` would charge the wrong
+        // account (or the AI's opponent) instead of the AI itself.
+        let mover = getPlayer(battle.players[player as usize]);
+
+        if choice == 2 || mover.player_mana >= 3 {
+            let mut battle = battle.clone();
             battle.moves[player as usize] = choice;
             update_battle(battle_name, &battle);
         }
@@ -247,7 +467,22 @@ fn register_player_move(player: u8, choice: u8, battle_name: &str) {
     }
 }
 
-fn attack_or_defend_choice(choice: u8, battle_name: &str) {
+// Public entry point for commit-reveal phase 1: each player calls this with
+// `keccak256(choice || salt)` before either side reveals.
+fn commit_attack_or_defend_choice(commit_hash: [u8; 32], battle_name: &str) {
+    let battle = get_battle(battle_name).unwrap();
+
+    if battle.battle_status == BattleStatus::STARTED
+        && (battle.players[0] == sender || battle.players[1] == sender)
+    {
+        commit_player_move((battle.players[0] == sender) as u8, commit_hash, battle_name);
+    } else {
+        // Handle error condition here
+        // e.g., return an error struct or panic
+    }
+}
+
+fn attack_or_defend_choice(choice: u8, salt: [u8; 32], battle_name: &str, ctx: &EventContext) {
     let mut battle = get_battle(battle_name).unwrap();
 
     if battle.battle_status == BattleStatus::STARTED
@@ -255,16 +490,18 @@ fn attack_or_defend_choice(choice: u8, battle_name: &str) {
         && (battle.players[0] == sender || battle.players[1] == sender)
     {
         if battle.moves[(battle.players[0] == sender) as usize] == 0 {
-            register_player_move((battle.players[0] == sender) as u8, choice, battle_name);
+            reveal_player_move((battle.players[0] == sender) as u8, choice, salt, battle_name);
 
             let battle = get_battle(battle_name).unwrap();
             let moves_left = 2 - (battle.moves[0] == 0) as u8 - (battle.moves[1] == 0) as u8;
 
-            // Emit BattleMove event (you would need to implement event handling)
-            // emit_battle_move(battle_name, moves_left == 1);
+            emit_event!(ctx, GameEvent::BattleMove {
+                name: battle_name.to_string(),
+                move_pending: moves_left != 0,
+            });
 
             if moves_left == 0 {
-                await_battle_results(battle_name);
+                await_battle_results(battle_name, ctx);
             }
         } else {
             // Handle error condition here
@@ -276,12 +513,12 @@ fn attack_or_defend_choice(choice: u8, battle_name: &str) {
     }
 }
 
-fn await_battle_results(battle_name: &str) {
+fn await_battle_results(battle_name: &str, ctx: &EventContext) {
     let battle = get_battle(battle_name).unwrap();
 
     if battle.players[0] == sender || battle.players[1] == sender {
         if battle.moves[0] != 0 && battle.moves[1] != 0 {
-            resolve_battle(&battle);
+            resolve_battle(&battle, ctx);
         } else {
             // Handle error condition here
             // e.g., return an error struct or panic
@@ -298,15 +535,17 @@ struct P {
     health: u256,
     attack: u256,
     defense: u256,
+    initiative: u256,
 }
 
-fn resolve_battle(battle: &Battle) {
+fn resolve_battle(battle: &Battle, ctx: &EventContext) {
     let p1 = P {
         index: PLAYER_INFO.get(&battle.players[0]).unwrap().clone(),
         move: battle.moves[0].clone(),
         health: getPlayer(battle.players[0]).player_health.clone(),
         attack: getPlayer_token(battle.players[0]).attack_strength.clone(),
         defense: getPlayer_token(battle.players[0]).defense_strength.clone(),
+        initiative: getPlayer_token(battle.players[0]).initiative.clone(),
     };
 
     let p2 = P {
@@ -315,18 +554,32 @@ fn resolve_battle(battle: &Battle) {
         health: getPlayer(battle.players[1]).player_health.clone(),
         attack: getPlayer_token(battle.players[1]).attack_strength.clone(),
         defense: getPlayer_token(battle.players[1]).defense_strength.clone(),
+        initiative: getPlayer_token(battle.players[1]).initiative.clone(),
     };
 
     let mut damaged_players: [Address; 2] = [Address::from([0u8; 20]), Address::from([0u8; 20])];
 
     if p1.move == 1 && p2.move == 1 {
-        if p1.attack >= p2.health {
-            end_battle(battle.players[0], battle);
-        } else if p2.attack >= p1.health {
-            end_battle(battle.players[1], battle);
+        let p1_effective_attack = effective_attack(getPlayer_token(battle.players[0]), getPlayer_token(battle.players[1]));
+        let p2_effective_attack = effective_attack(getPlayer_token(battle.players[1]), getPlayer_token(battle.players[0]));
+
+        // Higher initiative strikes first and can end the battle outright
+        // before the slower player gets to return the hit.
+        let p1_first = p1.initiative >= p2.initiative;
+        let (first_addr, first_index, first_attack_power, second_addr, second_index, second_attack_power, first_health, second_health) =
+            if p1_first {
+                (battle.players[0], p1.index, p1_effective_attack, battle.players[1], p2.index, p2_effective_attack, p1.health, p2.health)
+            } else {
+                (battle.players[1], p2.index, p2_effective_attack, battle.players[0], p1.index, p1_effective_attack, p2.health, p1.health)
+            };
+
+        if first_attack_power >= second_health {
+            end_battle(first_addr, battle, ctx);
+        } else if second_attack_power >= first_health {
+            end_battle(second_addr, battle, ctx);
         } else {
-            PLAYERS[p1.index as usize].player_health -= p2.attack;
-            PLAYERS[p2.index as usize].player_health -= p1.attack;
+            PLAYERS[second_index as usize].player_health -= first_attack_power;
+            PLAYERS[first_index as usize].player_health -= second_attack_power;
 
             PLAYERS[p1.index as usize].player_mana -= 3;
             PLAYERS[p2.index as usize].player_mana -= 3;
@@ -335,16 +588,17 @@ fn resolve_battle(battle: &Battle) {
             damaged_players = battle.players;
         }
     } else if p1.move == 1 && p2.move == 2 {
+        let p1_effective_attack = effective_attack(getPlayer_token(battle.players[0]), getPlayer_token(battle.players[1]));
         let phad = p2.health + p2.defense;
-        if p1.attack >= phad {
-            end_battle(battle.players[0], battle);
+        if p1_effective_attack >= phad {
+            end_battle(battle.players[0], battle, ctx);
         } else {
             let health_after_attack;
 
-            if p2.defense > p1.attack {
+            if p2.defense > p1_effective_attack {
                 health_after_attack = p2.health;
             } else {
-                health_after_attack = phad - p1.attack;
+                health_after_attack = phad - p1_effective_attack;
 
                 // Player 2 health damaged
                 damaged_players[0] = battle.players[1];
@@ -353,19 +607,20 @@ fn resolve_battle(battle: &Battle) {
             PLAYERS[p2.index as usize].player_health = health_after_attack;
 
             PLAYERS[p1.index as usize].player_mana -= 3;
-            PLAYERS[p2.index as usize].player_mana += 3;
+            PLAYERS[p2.index as usize].player_mana = add_mana(PLAYERS[p2.index as usize].player_mana.clone(), 3u256);
         }
     } else if p1.move == 2 && p2.move == 1 {
+        let p2_effective_attack = effective_attack(getPlayer_token(battle.players[1]), getPlayer_token(battle.players[0]));
         let phad = p1.health + p1.defense;
-        if p2.attack >= phad {
-            end_battle(battle.players[1], battle);
+        if p2_effective_attack >= phad {
+            end_battle(battle.players[1], battle, ctx);
         } else {
             let health_after_attack;
 
-            if p1.defense > p2.attack {
+            if p1.defense > p2_effective_attack {
                 health_after_attack = p1.health;
             } else {
-                health_after_attack = phad - p2.attack;
+                health_after_attack = phad - p2_effective_attack;
 
                 // Player 1 health damaged
                 damaged_players[0] = battle.players[0];
@@ -373,36 +628,433 @@ fn resolve_battle(battle: &Battle) {
 
             PLAYERS[p1.index as usize].player_health = health_after_attack;
 
-            PLAYERS[p1.index as usize].player_mana += 3;
+            PLAYERS[p1.index as usize].player_mana = add_mana(PLAYERS[p1.index as usize].player_mana.clone(), 3u256);
             PLAYERS[p2.index as usize].player_mana -= 3;
         }
     } else if p1.move == 2 && p2.move == 2 {
-        PLAYERS[p1.index as usize].player_mana += 3;
-        PLAYERS[p2.index as usize].player_mana += 3;
+        PLAYERS[p1.index as usize].player_mana = add_mana(PLAYERS[p1.index as usize].player_mana.clone(), 3u256);
+        PLAYERS[p2.index as usize].player_mana = add_mana(PLAYERS[p2.index as usize].player_mana.clone(), 3u256);
     }
 
-    // Emit RoundEnded event (you would need to implement event handling)
-    // emit_round
-    // Ended event
-    // emit_round_ended(damaged_players);
+    emit_event!(ctx, GameEvent::RoundEnded { damaged_players });
 
-    // Reset moves to 0
+    // Reset moves and commit-reveal state, advance to the next round
     let mut battle = get_battle(battle_name).unwrap();
     battle.moves[0] = 0;
     battle.moves[1] = 0;
+    battle.move_commits = [[0u8; 32]; 2];
+    battle.round_counter += 1u256;
     update_battle(battle_name, &battle);
 
-    // Reset random attack and defense strength
-    let random_attack_strength_player1 = create_random_num(MAX_ATTACK_DEFEND_STRENGTH, &battle.players[0]);
+    // Reset attack/defense strength from the same commit-bound entropy that
+    // resolved this round, so the numbers are reproducible from public state.
+    let random_attack_strength_player1 = round_random_num(&battle, battle.players[0], MAX_ATTACK_DEFEND_STRENGTH);
     GAME_TOKENS[PLAYER_TOKEN_INFO.get(&battle.players[0]).unwrap().clone()].attack_strength = random_attack_strength_player1.clone();
     GAME_TOKENS[PLAYER_TOKEN_INFO.get(&battle.players[0]).unwrap().clone()].defense_strength = MAX_ATTACK_DEFEND_STRENGTH - random_attack_strength_player1.clone();
 
-    let random_attack_strength_player2 = create_random_num(MAX_ATTACK_DEFEND_STRENGTH, &battle.players[1]);
+    let random_attack_strength_player2 = round_random_num(&battle, battle.players[1], MAX_ATTACK_DEFEND_STRENGTH);
     GAME_TOKENS[PLAYER_TOKEN_INFO.get(&battle.players[1]).unwrap().clone()].attack_strength = random_attack_strength_player2.clone();
     GAME_TOKENS[PLAYER_TOKEN_INFO.get(&battle.players[1]).unwrap().clone()].defense_strength = MAX_ATTACK_DEFEND_STRENGTH - random_attack_strength_player2.clone();
+
+    // Revealed salts are single-use: clear them once they've seeded this
+    // round's re-roll so they can't be replayed into the next one.
+    let mut battle = get_battle(battle_name).unwrap();
+    battle.revealed_salts = [[0u8; 32]; 2];
+    update_battle(battle_name, &battle);
+}
+
+#[derive(Clone)]
+struct TokenState {
+    id: u256,
+    attack_strength: u256,
+    defense_strength: u256,
+    initiative: u256,
+}
+
+#[derive(Clone)]
+struct PlayerState {
+    health: u256,
+    mana: u256,
+    token: TokenState,
+}
+
+// Self-contained, cloneable snapshot of a battle's combat-relevant state.
+// `resolve_round` below only ever touches this struct -- never
+// PLAYERS/GAME_TOKENS -- so it can be cloned and replayed as many times as
+// a simulator needs without corrupting live game storage.
+#[derive(Clone)]
+struct BattleState {
+    players: [PlayerState; 2],
+    status: BattleStatus,
+    winner: Option<u8>,
+}
+
+impl BattleState {
+    fn from_battle(battle: &Battle) -> BattleState {
+        let player_state = |addr: Address| PlayerState {
+            health: getPlayer(addr).player_health.clone(),
+            mana: getPlayer(addr).player_mana.clone(),
+            token: TokenState {
+                id: getPlayer_token(addr).id.clone(),
+                attack_strength: getPlayer_token(addr).attack_strength.clone(),
+                defense_strength: getPlayer_token(addr).defense_strength.clone(),
+                initiative: getPlayer_token(addr).initiative.clone(),
+            },
+        };
+
+        BattleState {
+            players: [player_state(battle.players[0]), player_state(battle.players[1])],
+            status: BattleStatus::STARTED,
+            winner: None,
+        }
+    }
+}
+
+fn effective_attack_state(attacker: &TokenState, defender: &TokenState) -> u256 {
+    let modifier = element_modifier(Element::from_id(attacker.id), Element::from_id(defender.id));
+    attacker.attack_strength.clone() * modifier
+}
+
+// Resolves one round of combat against a `BattleState` snapshot, mirroring
+// the attack/defend matrix, elemental modifiers and initiative order used by
+// `resolve_battle`, but against plain struct fields instead of global
+// storage. Returns `[p1_damaged, p2_damaged]`.
+fn resolve_round(state: &mut BattleState, p1_move: u8, p2_move: u8) -> [bool; 2] {
+    let mut damaged = [false, false];
+
+    if p1_move == 1 && p2_move == 1 {
+        let p1_attack = effective_attack_state(&state.players[0].token, &state.players[1].token);
+        let p2_attack = effective_attack_state(&state.players[1].token, &state.players[0].token);
+
+        let p1_first = state.players[0].token.initiative >= state.players[1].token.initiative;
+        let (first, first_attack, second, second_attack) = if p1_first {
+            (0usize, p1_attack, 1usize, p2_attack)
+        } else {
+            (1usize, p2_attack, 0usize, p1_attack)
+        };
+
+        if first_attack >= state.players[second].health {
+            state.status = BattleStatus::ENDED;
+            state.winner = Some(first as u8);
+        } else if second_attack >= state.players[first].health {
+            state.status = BattleStatus::ENDED;
+            state.winner = Some(second as u8);
+        } else {
+            state.players[second].health -= first_attack;
+            state.players[first].health -= second_attack;
+            state.players[0].mana -= 3;
+            state.players[1].mana -= 3;
+            damaged = [true, true];
+        }
+    } else if p1_move == 1 && p2_move == 2 {
+        let p1_attack = effective_attack_state(&state.players[0].token, &state.players[1].token);
+        let phad = state.players[1].health.clone() + state.players[1].token.defense_strength.clone();
+
+        if p1_attack >= phad {
+            state.status = BattleStatus::ENDED;
+            state.winner = Some(0);
+        } else {
+            if state.players[1].token.defense_strength <= p1_attack {
+                state.players[1].health = phad - p1_attack;
+                damaged[1] = true;
+            }
+            state.players[0].mana -= 3;
+            state.players[1].mana = add_mana(state.players[1].mana.clone(), 3u256);
+        }
+    } else if p1_move == 2 && p2_move == 1 {
+        let p2_attack = effective_attack_state(&state.players[1].token, &state.players[0].token);
+        let phad = state.players[0].health.clone() + state.players[0].token.defense_strength.clone();
+
+        if p2_attack >= phad {
+            state.status = BattleStatus::ENDED;
+            state.winner = Some(1);
+        } else {
+            if state.players[0].token.defense_strength <= p2_attack {
+                state.players[0].health = phad - p2_attack;
+                damaged[0] = true;
+            }
+            state.players[0].mana = add_mana(state.players[0].mana.clone(), 3u256);
+            state.players[1].mana -= 3;
+        }
+    } else if p1_move == 2 && p2_move == 2 {
+        state.players[0].mana = add_mana(state.players[0].mana.clone(), 3u256);
+        state.players[1].mana = add_mana(state.players[1].mana.clone(), 3u256);
+    }
+
+    damaged
+}
+
+// A Monte Carlo Tree Search node. `children` is keyed by move (1 = attack,
+// 2 = defend); `untried_moves` seeds expansion so every legal move is tried
+// at least once before UCB1 starts guiding selection.
+struct MctsNode {
+    battle_snapshot: BattleState,
+    visit_count: u32,
+    score_sum: f64,
+    children: HashMap<u8, MctsNode>,
+    untried_moves: Vec<u8>,
+}
+
+impl MctsNode {
+    fn new(battle_snapshot: BattleState) -> MctsNode {
+        let untried_moves = if battle_snapshot.status == BattleStatus::ENDED {
+            Vec::new()
+        } else {
+            vec![1u8, 2u8]
+        };
+
+        MctsNode {
+            battle_snapshot,
+            visit_count: 0,
+            score_sum: 0.0,
+            children: HashMap::new(),
+            untried_moves,
+        }
+    }
+}
+
+const MCTS_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+fn ucb1(child: &MctsNode, parent_visits: u32) -> f64 {
+    if child.visit_count == 0 {
+        return f64::INFINITY;
+    }
+    let avg_score = child.score_sum / child.visit_count as f64;
+    avg_score + MCTS_EXPLORATION * ((parent_visits as f64).ln() / child.visit_count as f64).sqrt()
 }
 
-fn quit_battle(battle_name: &str) {
+// Random legal-move rollout from `state` to a terminal state, scoring +1 if
+// `ai_player` ends up the winner, else 0.
+fn simulate_rollout(state: &BattleState, ai_player: u8) -> f64 {
+    let mut state = state.clone();
+    let mut rng = rand::thread_rng();
+
+    // Same cap as `simulate_matchups` -- without it a long defend/defend
+    // streak can run unbounded, well past `choose_ai_move`'s `time_budget`,
+    // which is only checked between whole MCTS iterations.
+    let mut rounds = 0u32;
+    while state.status != BattleStatus::ENDED && rounds < 1000 {
+        // Attacking costs 3 mana, so a player below that can only defend.
+        let p1_move = if state.players[0].mana >= 3u256 && rng.gen_bool(0.5) { 1 } else { 2 };
+        let p2_move = if state.players[1].mana >= 3u256 && rng.gen_bool(0.5) { 1 } else { 2 };
+        resolve_round(&mut state, p1_move, p2_move);
+        rounds += 1;
+    }
+
+    if state.winner == Some(ai_player) {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+// One selection -> expansion -> simulation -> backpropagation pass, written
+// recursively so backpropagation falls out of the call stack unwinding
+// rather than needing an explicit path vector.
+fn mcts_iteration(node: &mut MctsNode, ai_player: u8) -> f64 {
+    let score = if node.battle_snapshot.status == BattleStatus::ENDED {
+        if node.battle_snapshot.winner == Some(ai_player) {
+            1.0
+        } else {
+            0.0
+        }
+    } else if !node.untried_moves.is_empty() {
+        let move_choice = node.untried_moves.pop().unwrap();
+        let mut child_state = node.battle_snapshot.clone();
+
+        let mut rng = rand::thread_rng();
+        let opponent_move = if rng.gen_bool(0.5) { 1 } else { 2 };
+        let (p1_move, p2_move) = if ai_player == 0 {
+            (move_choice, opponent_move)
+        } else {
+            (opponent_move, move_choice)
+        };
+        resolve_round(&mut child_state, p1_move, p2_move);
+
+        let rollout_score = simulate_rollout(&child_state, ai_player);
+        let mut child = MctsNode::new(child_state);
+        child.visit_count = 1;
+        child.score_sum = rollout_score;
+        node.children.insert(move_choice, child);
+
+        rollout_score
+    } else {
+        let parent_visits = node.visit_count;
+        let best_move = *node
+            .children
+            .iter()
+            .max_by(|a, b| ucb1(a.1, parent_visits).partial_cmp(&ucb1(b.1, parent_visits)).unwrap())
+            .unwrap()
+            .0;
+        mcts_iteration(node.children.get_mut(&best_move).unwrap(), ai_player)
+    };
+
+    node.visit_count += 1;
+    node.score_sum += score;
+    score
+}
+
+// Picks the AI's attack (1) / defend (2) move for `players[1]` via MCTS,
+// spending up to `time_budget` on iterations and returning the root child
+// with the highest visit count (the standard "most robust move" choice,
+// more stable under noise than picking the highest average score).
+fn choose_ai_move(battle: BattleState, time_budget: Duration) -> u8 {
+    const AI_PLAYER: u8 = 1;
+
+    let mut root = MctsNode::new(battle);
+    let deadline = Instant::now() + time_budget;
+
+    while Instant::now() < deadline {
+        mcts_iteration(&mut root, AI_PLAYER);
+    }
+
+    root.children
+        .iter()
+        .max_by_key(|(_, child)| child.visit_count)
+        .map(|(mv, _)| *mv)
+        .unwrap_or(2)
+}
+
+// Deterministic salt for the AI's own commit-reveal step. The AI has no
+// secret to protect from itself, but it still has to go through the same
+// commit-then-reveal dance as a human player so its move never sits in
+// world-readable `battle.moves` before it has committed.
+fn ai_move_salt(battle: &Battle) -> [u8; 32] {
+    let mut round_bytes = [0u8; 32];
+    battle.round_counter.to_big_endian(&mut round_bytes);
+
+    let mut hasher = Keccak256::new();
+    hasher.update(battle.battle_hash);
+    hasher.update(round_bytes);
+    hasher.update(b"ai-move-salt");
+    hasher.finalize().into()
+}
+
+// Lets a game session seat an AI in `players[1]`: snapshots the live battle
+// into a `BattleState`, runs MCTS to pick a move, then commits and reveals it
+// through the same commit-reveal path a human player uses.
+fn take_ai_turn(battle_name: &str, time_budget: Duration, ctx: &EventContext) {
+    let battle = get_battle(battle_name).unwrap();
+
+    // Refuse to move until the human has already committed -- acting first
+    // would let the human read the AI's revealed move out of `battle.moves`
+    // and then commit whichever choice beats it, turning "single-player"
+    // into a fight against information leakage rather than the AI.
+    if battle.move_commits[0] == [0u8; 32] {
+        return;
+    }
+
+    let state = BattleState::from_battle(battle);
+    let ai_move = choose_ai_move(state, time_budget);
+    let salt = ai_move_salt(battle);
+
+    let mut hasher = Keccak256::new();
+    hasher.update([ai_move]);
+    hasher.update(salt);
+    let commit_hash: [u8; 32] = hasher.finalize().into();
+
+    commit_player_move(1, commit_hash, battle_name);
+    reveal_player_move(1, ai_move, salt, battle_name);
+
+    let battle = get_battle(battle_name).unwrap();
+    if battle.moves[0] != 0 && battle.moves[1] != 0 {
+        await_battle_results(battle_name, ctx);
+    }
+}
+
+// Seeded partial Fisher-Yates: swaps `slice[i]` with a uniformly chosen
+// index in `[i, len)` for each of the first `k` positions, leaving the rest
+// untouched. Shared by the matchup simulator for both randomizing initial
+// token assignment and picking random moves.
+fn partial_shuffle<T>(slice: &mut [T], k: usize, rng: &mut impl Rng) {
+    let len = slice.len();
+    for i in 0..k.min(len) {
+        let j = rng.gen_range(i..len);
+        slice.swap(i, j);
+    }
+}
+
+fn random_move(rng: &mut impl Rng) -> u8 {
+    let mut moves = [1u8, 2u8];
+    partial_shuffle(&mut moves, 1, rng);
+    moves[0]
+}
+
+fn synthetic_player(token_id: u8, rng: &mut impl Rng) -> PlayerState {
+    let attack_strength: u256 = rng.gen_range(1u64..MAX_ATTACK_DEFEND_STRENGTH.as_u64()).into();
+    let defense_strength = MAX_ATTACK_DEFEND_STRENGTH.clone() - attack_strength.clone();
+
+    PlayerState {
+        health: 25u256,
+        mana: 10u256,
+        token: TokenState {
+            id: token_id.into(),
+            attack_strength,
+            defense_strength,
+            initiative: token_id.into(),
+        },
+    }
+}
+
+// Offline balance harness: plays `samples` battles for every ordered token
+// archetype pair and reports how often the first id beats the second, so
+// designers can spot a dominant token before `MAX_ATTACK_DEFEND_STRENGTH` or
+// the elemental modifiers ship. Runs entirely on `BattleState`, reusing the
+// same `resolve_round` core as the MCTS simulator, so it never touches
+// PLAYERS/GAME_TOKENS. The whole run is reproducible from `seed`.
+fn simulate_matchups(samples: usize, seed: u64) -> HashMap<(u256, u256), f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut results = HashMap::new();
+
+    // Minted tokens get `rand_id` in `1..=6` (see `create_game_token`), and
+    // `initiative` is set equal to that same id -- so id `6` (Devil, since
+    // `Element::from_id` reduces mod 6) is production's highest-initiative,
+    // always-strikes-first archetype. Mirror that range here; starting from
+    // 0 would test a "Devil" that never strikes first and can't exist.
+    for a in 1u8..=6u8 {
+        for b in 1u8..=6u8 {
+            if a == b {
+                continue;
+            }
+
+            let mut wins = 0usize;
+            for _ in 0..samples {
+                let mut token_ids = [a, b];
+                // Randomize which physical slot (player1/player2) holds
+                // which archetype so a positional quirk in resolve_round
+                // can't masquerade as a balance issue.
+                partial_shuffle(&mut token_ids, 1, &mut rng);
+
+                let mut state = BattleState {
+                    players: [
+                        synthetic_player(token_ids[0], &mut rng),
+                        synthetic_player(token_ids[1], &mut rng),
+                    ],
+                    status: BattleStatus::STARTED,
+                    winner: None,
+                };
+
+                let mut rounds = 0u32;
+                while state.status != BattleStatus::ENDED && rounds < 1000 {
+                    let p1_move = random_move(&mut rng);
+                    let p2_move = random_move(&mut rng);
+                    resolve_round(&mut state, p1_move, p2_move);
+                    rounds += 1;
+                }
+
+                if state.winner.map(|w| token_ids[w as usize]) == Some(a) {
+                    wins += 1;
+                }
+            }
+
+            results.insert((a.into(), b.into()), wins as f64 / samples as f64);
+        }
+    }
+
+    results
+}
+
+fn quit_battle(battle_name: &str, ctx: &EventContext) {
     let mut battle = get_battle(battle_name).unwrap();
     if battle.players[0] == sender || battle.players[1] == sender {
         let battle_loser = if battle.players[0] == sender {
@@ -411,11 +1063,11 @@ fn quit_battle(battle_name: &str) {
             battle.players[0]
         };
 
-        end_battle(battle_loser, battle);
+        end_battle(battle_loser, battle, ctx);
     }
 }
 
-fn end_battle(battle_ender: Address, mut battle: Battle) {
+fn end_battle(battle_ender: Address, mut battle: Battle, ctx: &EventContext) {
     if battle.battle_status != BattleStatus::ENDED {
         battle.battle_status = BattleStatus::ENDED;
         battle.winner = battle_ender.clone();
@@ -438,24 +1090,14 @@ fn end_battle(battle_ender: Address, mut battle: Battle) {
             battle.players[0]
         };
 
-        // Emit BattleEnded event (you would need to implement event handling)
-        // emit_battle_ended(battle.name, battle_ender, battle_loser);
-        emit_battle_ended(battle.name.clone(), battle_ender.clone(), battle_loser.clone());
+        emit_event!(ctx, GameEvent::BattleEnded {
+            name: battle.name.clone(),
+            winner: battle_ender,
+            loser: battle_loser,
+        });
     }
 }
 
-// Implement the uintToStr function in Rust
-
-// Implement the token_uri function in Rust
-
-// Implement the _before_token_transfer function in Rust
-
-// You will also need to implement any missing structs and enums, and handle event emissions, storage, and other contract-specific logic.
-    // Emit BattleEnded event (you would need to implement event handling)
-    
-
-
-// Implement the uintToStr function in Rust
 fn uint_to_str(n: u256) -> String {
     if n == 0u256 {
         return "0".to_string();
@@ -497,3 +1139,233 @@ fn _before_token_transfer(
 ) {
     super::_before_token_transfer(operator, from, to, ids.clone(), amounts.clone(), data.clone());
 }
+
+// Big-endian bit accumulator used to pack a `Battle` (plus its two players
+// and tokens) into a minimal byte stream for cheap off-chain distribution:
+// a read-only snapshot for observers/UI/peer-to-peer sync, not a format for
+// round-tripping a battle back into live storage -- see the caveat on
+// `decode_battle` below. Variable-width fields are packed bit-by-bit;
+// anything that's already a fixed byte blob (addresses, the battle hash) is
+// written `byte_align()`ed so it can be sliced out directly on decode.
+struct BitPackedBuffer {
+    bytes: Vec<u8>,
+    bit_cursor: u8,
+}
+
+impl BitPackedBuffer {
+    fn new() -> BitPackedBuffer {
+        BitPackedBuffer { bytes: Vec::new(), bit_cursor: 0 }
+    }
+
+    fn write_bits(&mut self, value: u64, n: u8) {
+        for i in (0..n).rev() {
+            if self.bit_cursor == 0 {
+                self.bytes.push(0u8);
+            }
+
+            let bit = ((value >> i) & 1) as u8;
+            let byte = self.bytes.last_mut().unwrap();
+            *byte |= bit << (7 - self.bit_cursor);
+
+            self.bit_cursor = (self.bit_cursor + 1) % 8;
+        }
+    }
+
+    fn byte_align(&mut self) {
+        self.bit_cursor = 0;
+    }
+
+    fn write_bytes_aligned(&mut self, data: &[u8]) {
+        self.byte_align();
+        self.bytes.extend_from_slice(data);
+    }
+
+    fn into_vec(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+// Returned instead of panicking when a decode reads past the end of the
+// buffer, e.g. from a truncated peer-to-peer payload.
+#[derive(Debug)]
+struct DecodeError;
+
+struct BitPackedReader<'a> {
+    bytes: &'a [u8],
+    byte_pos: usize,
+    bit_cursor: u8,
+}
+
+impl<'a> BitPackedReader<'a> {
+    fn new(bytes: &'a [u8]) -> BitPackedReader<'a> {
+        BitPackedReader { bytes, byte_pos: 0, bit_cursor: 0 }
+    }
+
+    fn read_bits(&mut self, n: u8) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+
+        for _ in 0..n {
+            let byte = self.bytes.get(self.byte_pos).ok_or(DecodeError)?;
+            let bit = (byte >> (7 - self.bit_cursor)) & 1;
+            value = (value << 1) | bit as u64;
+
+            self.bit_cursor += 1;
+            if self.bit_cursor == 8 {
+                self.bit_cursor = 0;
+                self.byte_pos += 1;
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn byte_align(&mut self) {
+        if self.bit_cursor != 0 {
+            self.bit_cursor = 0;
+            self.byte_pos += 1;
+        }
+    }
+
+    fn read_bytes_aligned(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        self.byte_align();
+
+        let end = self.byte_pos + n;
+        if end > self.bytes.len() {
+            return Err(DecodeError);
+        }
+
+        let slice = &self.bytes[self.byte_pos..end];
+        self.byte_pos = end;
+        Ok(slice)
+    }
+}
+
+fn battle_status_to_bits(status: BattleStatus) -> u64 {
+    match status {
+        BattleStatus::PENDING => 0,
+        BattleStatus::STARTED => 1,
+        BattleStatus::ENDED => 2,
+    }
+}
+
+fn battle_status_from_bits(bits: u64) -> BattleStatus {
+    match bits {
+        0 => BattleStatus::PENDING,
+        1 => BattleStatus::STARTED,
+        _ => BattleStatus::ENDED,
+    }
+}
+
+// Packs a battle plus its two participants into a compact byte stream for
+// cheap storage and peer-to-peer sync; `decode_battle` reads it back.
+// Names are not carried in the compact form -- they're looked up by the
+// battle/player index on the receiving end, same as on-chain storage does.
+fn encode_battle(battle: &Battle, players: &[Player; 2], tokens: &[GameToken; 2]) -> Vec<u8> {
+    let mut buf = BitPackedBuffer::new();
+
+    buf.write_bits(battle_status_to_bits(battle.battle_status), 2);
+    buf.write_bits(battle.moves[0] as u64, 2);
+    buf.write_bits(battle.moves[1] as u64, 2);
+    // 32 bits comfortably outlives any real battle -- `simulate_matchups`
+    // itself caps a battle at 1000 rounds.
+    buf.write_bits(battle.round_counter.as_u64(), 32);
+
+    buf.write_bytes_aligned(&battle.battle_hash);
+    buf.write_bytes_aligned(battle.players[0].as_bytes());
+    buf.write_bytes_aligned(battle.players[1].as_bytes());
+    buf.write_bytes_aligned(battle.winner.as_bytes());
+    buf.write_bytes_aligned(&battle.move_commits[0]);
+    buf.write_bytes_aligned(&battle.move_commits[1]);
+    buf.write_bytes_aligned(&battle.revealed_salts[0]);
+    buf.write_bytes_aligned(&battle.revealed_salts[1]);
+
+    for i in 0..2 {
+        // Health tops out at the post-battle reset value of 25, mana is
+        // clamped to MAX_MANA (15) by `add_mana`, attack/defense top out at
+        // MAX_ATTACK_DEFEND_STRENGTH (10), and ids run 0-6 -- 5/4/4/4/3 bits
+        // cover all of them with no wasted byte.
+        buf.write_bits(players[i].player_health.as_u64(), 5);
+        buf.write_bits(players[i].player_mana.as_u64(), 4);
+        buf.write_bits(tokens[i].attack_strength.as_u64(), 4);
+        buf.write_bits(tokens[i].defense_strength.as_u64(), 4);
+        buf.write_bits(tokens[i].id.as_u64(), 3);
+    }
+
+    buf.into_vec()
+}
+
+// Inverse of `encode_battle`. Errors instead of panicking when `bytes` is
+// shorter than the encoding expects. The battle, player and token names come
+// back empty, `in_battle` is set from the battle status, and `initiative` is
+// set equal to the token id; every other field is restored as encoded.
+fn decode_battle(bytes: &[u8]) -> Result<(Battle, [Player; 2], [GameToken; 2]), DecodeError> {
+    let mut reader = BitPackedReader::new(bytes);
+
+    let status = battle_status_from_bits(reader.read_bits(2)?);
+    let move0 = reader.read_bits(2)? as u8;
+    let move1 = reader.read_bits(2)? as u8;
+    let round_counter: u256 = reader.read_bits(32)?.into();
+
+    let battle_hash_bytes = reader.read_bytes_aligned(32)?;
+    let mut battle_hash = [0u8; 32];
+    battle_hash.copy_from_slice(battle_hash_bytes);
+
+    let player_addrs = [
+        Address::from_slice(reader.read_bytes_aligned(20)?),
+        Address::from_slice(reader.read_bytes_aligned(20)?),
+    ];
+    let winner = Address::from_slice(reader.read_bytes_aligned(20)?);
+
+    let mut move_commits = [[0u8; 32]; 2];
+    move_commits[0].copy_from_slice(reader.read_bytes_aligned(32)?);
+    move_commits[1].copy_from_slice(reader.read_bytes_aligned(32)?);
+
+    let mut revealed_salts = [[0u8; 32]; 2];
+    revealed_salts[0].copy_from_slice(reader.read_bytes_aligned(32)?);
+    revealed_salts[1].copy_from_slice(reader.read_bytes_aligned(32)?);
+
+    let mut players = Vec::with_capacity(2);
+    let mut tokens = Vec::with_capacity(2);
+
+    for i in 0..2 {
+        let health = reader.read_bits(5)?;
+        let mana = reader.read_bits(4)?;
+        let attack = reader.read_bits(4)?;
+        let defense = reader.read_bits(4)?;
+        let id = reader.read_bits(3)?;
+
+        players.push(Player {
+            player_address: player_addrs[i],
+            player_name: String::new(),
+            player_mana: mana.into(),
+            player_health: health.into(),
+            in_battle: status == BattleStatus::STARTED,
+        });
+
+        tokens.push(GameToken {
+            name: String::new(),
+            id: id.into(),
+            attack_strength: attack.into(),
+            defense_strength: defense.into(),
+            initiative: id.into(),
+        });
+    }
+
+    let battle = Battle {
+        battle_status: status,
+        battle_hash,
+        name: String::new(),
+        players: player_addrs,
+        moves: [move0, move1],
+        move_commits,
+        revealed_salts,
+        round_counter,
+        winner,
+    };
+
+    Ok((
+        battle,
+        players.try_into().unwrap_or_else(|_| unreachable!()),
+        tokens.try_into().unwrap_or_else(|_| unreachable!()),
+    ))
+}